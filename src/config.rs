@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Default delimiter characters: word separators in paths and identifiers.
+const DEFAULT_DELIMITER_CHARS: &[char] = &['/', '\\', '-', '_', '.', ',', ':', ';', '|'];
+
+/// Per-query flags and scoring weights for `FuzzyMatcher`, `ExactMatcher`, and
+/// `OptimalFuzzyMatcher`. `MatcherConfig::default()` reproduces the matchers'
+/// original hardcoded weights, so callers that don't care about tuning can ignore
+/// the weight fields entirely.
+#[derive(Debug, Clone)]
+pub struct MatcherConfig {
+    pub case_sensitive: bool,
+    pub match_position: bool,
+    /// When set, `Pattern` builds an `OptimalFuzzyMatcher` instead of the greedy
+    /// `FuzzyMatcher` for unquoted terms, trading some speed for a dynamic-programming
+    /// alignment instead of a greedy one. That alignment is not guaranteed to be the
+    /// highest-scoring one; see `OptimalFuzzyMatcher`.
+    pub optimal: bool,
+    /// When set, accented Latin-1 letters are mapped to their base letter (e.g.
+    /// 'é' -> 'e'), so "cafe" matches "café". The mapping covers lowercase forms
+    /// only, so an accented capital such as 'É' is stripped only when
+    /// `case_sensitive` is false and it has been case-folded first.
+    pub normalize_unicode: bool,
+
+    pub score_beginning: isize,
+    pub score_boundary: isize,
+    pub score_match: isize,
+    pub score_consecutive: isize,
+    pub gap_start: isize,
+    pub gap_extension: isize,
+    /// Subtracted from a match's bonus when the haystack char wasn't identical to
+    /// the pattern char it matched — i.e. the match only worked because
+    /// `case_sensitive` is false and `normalize` folded case (or diacritics) to
+    /// make them equal. 0 reproduces the matchers' original behavior of not
+    /// penalizing these matches. Only `FuzzyMatcher` and `ExactMatcher` apply this;
+    /// `OptimalFuzzyMatcher`'s DP precomputes bonuses before it knows which pattern
+    /// char aligns where.
+    pub case_mismatch_penalty: isize,
+
+    /// Characters classified as delimiters (word separators in paths and
+    /// identifiers). Boundary scoring treats them the same as any other non-word
+    /// char; a letter or digit listed here stops counting as a word char.
+    /// `Arc`-wrapped so cloning a `MatcherConfig` (e.g.
+    /// to flip `match_position` for a probe match) stays cheap.
+    pub delimiter_chars: Arc<HashSet<char>>,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        MatcherConfig {
+            case_sensitive: false,
+            match_position: false,
+            optimal: false,
+            normalize_unicode: false,
+
+            score_beginning: 20,
+            score_boundary: 10,
+            score_match: 3,
+            score_consecutive: 3,
+            gap_start: 6,
+            gap_extension: 1,
+            case_mismatch_penalty: 0,
+
+            delimiter_chars: Arc::new(DEFAULT_DELIMITER_CHARS.iter().copied().collect()),
+        }
+    }
+}
+
+/// On-disk representation of the tunable subset of `MatcherConfig`, deserialized
+/// from a TOML file such as `~/.config/fuzzyrs.toml`. Every field is optional so a
+/// config file only needs to override the weights it cares about; anything omitted
+/// falls back to `MatcherConfig::default()`.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct MatcherConfigFile {
+    case_sensitive: Option<bool>,
+    optimal: Option<bool>,
+    normalize_unicode: Option<bool>,
+
+    score_beginning: Option<isize>,
+    score_boundary: Option<isize>,
+    score_match: Option<isize>,
+    score_consecutive: Option<isize>,
+    gap_start: Option<isize>,
+    gap_extension: Option<isize>,
+    case_mismatch_penalty: Option<isize>,
+
+    /// A plain string of delimiter characters, e.g. `"/-_."`.
+    delimiter_chars: Option<String>,
+}
+
+impl MatcherConfigFile {
+    fn into_config(self) -> MatcherConfig {
+        let defaults = MatcherConfig::default();
+        MatcherConfig {
+            case_sensitive: self.case_sensitive.unwrap_or(defaults.case_sensitive),
+            match_position: defaults.match_position,
+            optimal: self.optimal.unwrap_or(defaults.optimal),
+            normalize_unicode: self.normalize_unicode.unwrap_or(defaults.normalize_unicode),
+
+            score_beginning: self.score_beginning.unwrap_or(defaults.score_beginning),
+            score_boundary: self.score_boundary.unwrap_or(defaults.score_boundary),
+            score_match: self.score_match.unwrap_or(defaults.score_match),
+            score_consecutive: self.score_consecutive.unwrap_or(defaults.score_consecutive),
+            gap_start: self.gap_start.unwrap_or(defaults.gap_start),
+            gap_extension: self.gap_extension.unwrap_or(defaults.gap_extension),
+            case_mismatch_penalty: self.case_mismatch_penalty.unwrap_or(defaults.case_mismatch_penalty),
+
+            delimiter_chars: match self.delimiter_chars {
+                Some(chars) => Arc::new(chars.chars().collect()),
+                None => defaults.delimiter_chars,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "failed to read config file: {}", e),
+            ConfigError::Parse(e) => write!(f, "failed to parse config file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Loads a `MatcherConfig` from the TOML file at `path`, falling back to
+/// `MatcherConfig::default()` if the file doesn't exist.
+pub fn load_config(path: &Path) -> Result<MatcherConfig, ConfigError> {
+    if !path.exists() {
+        return Ok(MatcherConfig::default());
+    }
+    let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+    let file: MatcherConfigFile = toml::from_str(&contents).map_err(ConfigError::Parse)?;
+    Ok(file.into_config())
+}
+
+/// The default config location read by the `fuzzyrs` binary: `~/.config/fuzzyrs.toml`.
+/// Returns `None` if `HOME` isn't set.
+pub fn default_config_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| Path::new(&home).join(".config").join("fuzzyrs.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_falls_back_to_defaults() {
+        let config = load_config(Path::new("/nonexistent/fuzzyrs.toml")).unwrap();
+        assert_eq!(config.score_beginning, MatcherConfig::default().score_beginning);
+    }
+
+    #[test]
+    fn file_overrides_only_specified_weights() {
+        let dir = env::temp_dir().join("fuzzyrs-config-test-file-overrides");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("fuzzyrs.toml");
+        fs::write(&path, "score_boundary = 42\ndelimiter_chars = \"/-\"\n").unwrap();
+
+        let config = load_config(&path).unwrap();
+        assert_eq!(config.score_boundary, 42);
+        assert_eq!(config.score_beginning, MatcherConfig::default().score_beginning);
+        assert!(config.delimiter_chars.contains(&'/'));
+        assert!(!config.delimiter_chars.contains(&'_'));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+pub mod config;
+pub mod matcher;
+pub mod pattern;
+pub mod search;
+pub mod unicode;
+
+pub use config::{load_config, default_config_path, ConfigError, MatcherConfig};
+pub use matcher::{ExactMatcher, FuzzyMatcher, MatchResult, Matcher, OptimalFuzzyMatcher};
+pub use pattern::Pattern;
+pub use search::{search, IncrementalSearcher, ScoredMatch};
+pub use unicode::Utf32Input;
@@ -1,9 +1,11 @@
-use crossbeam::channel;
 use std::env;
 use std::io::{self, Write};
 use walkdir::{DirEntry,  WalkDir};
-use rayon::prelude::*;
-use fuzzyrs::{Pattern, MatchOptions};
+use fuzzyrs::{search, Pattern, MatcherConfig};
+
+/// Results beyond this rank are dropped before printing, so a huge tree with a
+/// short, common query doesn't dump thousands of low-scoring matches to stdout.
+const RESULT_LIMIT: usize = 200;
 
 fn is_hidden(entry: &DirEntry) -> bool {
     entry.file_name()
@@ -12,44 +14,39 @@ fn is_hidden(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
+/// Loads scoring weights from `~/.config/fuzzyrs.toml` if present, falling back to
+/// `MatcherConfig::default()` on any error (missing `HOME`, unreadable file, bad
+/// TOML) so a broken config never stops the binary from running.
+fn load_config() -> MatcherConfig {
+    fuzzyrs::default_config_path()
+        .and_then(|path| fuzzyrs::load_config(&path).ok())
+        .unwrap_or_default()
+}
+
 fn read_from_current_dir() {
     let term = env::args().nth(1).unwrap();
-    
-    let options = MatchOptions::default();
-    let pattern = Pattern::new(&term, options);
-    
-    let (sender, receiver) = channel::unbounded::<String>();
+
+    let config = load_config();
+    let pattern = Pattern::new(&term, config);
 
     let current_dir = env::current_dir().unwrap();
     let path_len = current_dir.to_str().unwrap().len() + 1;
-    let input: Vec<DirEntry> = WalkDir::new(current_dir)
+    let candidates: Vec<String> = WalkDir::new(current_dir)
         .into_iter()
         .filter_entry(|e| !is_hidden(e))
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
+        .map(|entry| {
+            let full_path = entry.path().to_str().unwrap();
+            full_path[path_len..full_path.len()].to_string()
+        })
         .collect();
-    
-    let thread = std::thread::spawn(move || {
-        let stdout = io::stdout();
-        let mut writer = stdout.lock();
-        for line in receiver.into_iter() {
-            writeln!(writer, "{}", line).unwrap();
-        }
-    });
-
-    input.as_parallel_slice()
-        .par_chunks(32)
-        .for_each_with(sender, |sender, chunk| {
-            for entry in chunk {
-                let full_path = entry.path().to_str().unwrap();
-                let rel_path = &full_path[path_len..full_path.len()];
-                if pattern.matches(rel_path.as_bytes()).is_some() {
-                    sender.send(rel_path.to_string()).unwrap()
-                }
-            }
-        });
-
-    thread.join().unwrap();
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    for result in search(&pattern, &candidates, RESULT_LIMIT) {
+        writeln!(writer, "{}", result.candidate).unwrap();
+    }
 }
 
 fn main() {
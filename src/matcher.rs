@@ -1,4 +1,5 @@
-use super::unicode::next_code_point;
+use super::config::MatcherConfig;
+use super::unicode::{self, Utf32Input};
 
 #[derive(Debug)]
 pub struct MatchResult {
@@ -6,18 +7,50 @@ pub struct MatchResult {
     score: isize,
 }
 
-#[derive(Default, Debug, Clone, Copy)]
-pub struct MatchOptions {
-    pub case_sensitive: bool,
-    pub match_position: bool
+impl MatchResult {
+    pub(crate) fn new(score: isize, matches: Option<Vec<usize>>) -> Self {
+        MatchResult { score, matches }
+    }
+
+    pub fn score(&self) -> isize {
+        self.score
+    }
+
+    pub fn matches(&self) -> Option<&[usize]> {
+        self.matches.as_deref()
+    }
 }
 
-#[derive(Debug)]
-enum InputState {
-    Beginning,
-    InWord,
-    InSpace,
-    InSpecial,
+/// Normalizes a decoded code point the same way for pattern chars (at matcher
+/// construction) and haystack chars (while streaming through `match_term`), so the
+/// two sides are always compared on equal footing.
+#[inline]
+fn normalize(c: char, config: &MatcherConfig) -> char {
+    let c = if config.case_sensitive { c } else { unicode::fold_case(c) };
+    if config.normalize_unicode {
+        unicode::strip_diacritics(c)
+    } else {
+        c
+    }
+}
+
+/// Classifies a single character so `bonus_at` can detect boundaries between
+/// classes (e.g. the lower-to-upper transition inside camelCase identifiers), not
+/// just a coarse word/space/special split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Lower,
+    Upper,
+    /// A letter with no case distinction (CJK, Arabic, Hebrew, Devanagari, Thai,
+    /// etc.). Kept separate from `Lower`/`Upper` so it still counts as a word
+    /// character for the generic word/non-word boundary checks without spuriously
+    /// tripping the `Lower -> Upper` camelCase rule, which only makes sense for
+    /// scripts that actually have case.
+    Letter,
+    Number,
+    Whitespace,
+    Delimiter,
+    NonWord,
 }
 
 macro_rules! choose {
@@ -27,133 +60,360 @@ macro_rules! choose {
 }
 
 #[inline]
-fn state_from_char(c: char) -> InputState {
-    if c.is_alphanumeric() {
-        InputState::InWord
-    } else if c.is_whitespace() {
-        InputState::InSpace
+fn char_class(c: char, config: &MatcherConfig) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if config.delimiter_chars.contains(&c) {
+        CharClass::Delimiter
+    } else if c.is_numeric() {
+        CharClass::Number
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_alphabetic() {
+        CharClass::Letter
     } else {
-        InputState::InSpecial
+        CharClass::NonWord
     }
 }
 
-const SCORE_BEGINNING: isize = 20;
-const SCORE_BOUNDARY: isize = 10;
-const SCORE_MATCH: isize = 3;
-const SCORE_CONSECUTIVE: isize = 3;
+#[inline]
+fn is_word_class(class: CharClass) -> bool {
+    matches!(class, CharClass::Lower | CharClass::Upper | CharClass::Letter | CharClass::Number)
+}
 
+/// Very negative placeholder for "unreachable" DP cells. Using `isize::MIN` directly
+/// would overflow as soon as a gap penalty is subtracted from it.
+const UNREACHABLE: isize = isize::MIN / 2;
 
 #[inline]
-fn bonus_at(state: InputState, c: char, distance: usize) -> isize {
-    let mut score = match state {
-        InputState::Beginning => SCORE_BEGINNING,
-        InputState::InSpace => SCORE_BOUNDARY,
-        InputState::InSpecial => choose!(c.is_alphanumeric(), SCORE_BOUNDARY, SCORE_MATCH),
-        InputState::InWord =>  choose!(!c.is_alphanumeric(), SCORE_BOUNDARY, SCORE_MATCH),
+fn gap_penalty(len: usize, config: &MatcherConfig) -> isize {
+    config.gap_start + (len as isize - 1) * config.gap_extension
+}
+
+/// `prev` is the class of the char immediately before `raw` (`None` at the start of
+/// the input). Boundary bonuses are driven off the transition between the two
+/// classes rather than just `raw` in isolation, so "fb" scores the F and B in
+/// "FooBar" as boundary matches (lower -> upper), the same way a delimiter or space
+/// before a word would. `raw` and `pattern_char` are both pre-fold, so a case
+/// mismatch between them (only possible when `case_sensitive` is false) can still be
+/// detected and penalized after the fact.
+#[inline]
+fn bonus_at(prev: Option<CharClass>, raw: char, pattern_char: char, distance: usize, config: &MatcherConfig) -> isize {
+    let current = char_class(raw, config);
+    let mut score = match prev {
+        None => config.score_beginning,
+        Some(CharClass::Whitespace) => config.score_boundary,
+        Some(CharClass::Lower) if current == CharClass::Upper => config.score_boundary,
+        Some(p) if !is_word_class(p) && is_word_class(current) => config.score_boundary,
+        Some(p) if is_word_class(p) && current == CharClass::Number && p != CharClass::Number => config.score_boundary,
+        Some(p) if is_word_class(p) && !is_word_class(current) => config.score_boundary,
+        _ => config.score_match,
     };
     if distance == 1 {
-        score += SCORE_CONSECUTIVE;
+        score += config.score_consecutive;
+    }
+    if !config.case_sensitive && raw != pattern_char {
+        score -= config.case_mismatch_penalty;
     }
     score
 }
 
 pub trait Matcher {
-    fn match_term(&self, input: &[u8], opts: MatchOptions) -> Option<MatchResult>;
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult>;
+
+    /// Cheap check used to reject obvious non-matches before the (potentially much
+    /// more expensive) full scan in `match_term`. The default conservatively assumes
+    /// a match is still possible; implementations with an efficient prefilter
+    /// should override it.
+    fn could_match(&self, _input: &Utf32Input, _config: &MatcherConfig) -> bool {
+        true
+    }
+}
+
+/// Cheap prefilter shared by `FuzzyMatcher` and `OptimalFuzzyMatcher`: verifies every
+/// char of `term` appears in `haystack`, in order, via a single forward scan.
+fn could_match_subsequence(term: &[char], haystack: &[char], config: &MatcherConfig) -> bool {
+    if term.is_empty() || haystack.is_empty() {
+        return false;
+    }
+
+    let mut index = match find_first_char(term[0], haystack, config) {
+        Some(i) => i,
+        None => return false,
+    };
+    let mut term_index = 0;
+    while index < haystack.len() {
+        if normalize(haystack[index], config) == term[term_index] {
+            term_index += 1;
+            if term_index == term.len() {
+                return true;
+            }
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Finds the first occurrence of `target` (already normalized) in `haystack`.
+#[inline]
+fn find_first_char(target: char, haystack: &[char], config: &MatcherConfig) -> Option<usize> {
+    haystack.iter().position(|&c| normalize(c, config) == target)
 }
 
 #[derive(Debug)]
 pub struct FuzzyMatcher {
-    term: Vec<char>
+    term: Vec<char>,
+    term_raw: Vec<char>,
 }
 
 impl FuzzyMatcher {
-    pub fn new(term: &str) -> Self {
+    pub fn new(term: &str, config: &MatcherConfig) -> Self {
+        let term_raw: Vec<char> = term.chars().collect();
         FuzzyMatcher {
-            term: term.chars().collect()
+            term: term_raw.iter().map(|&c| normalize(c, config)).collect(),
+            term_raw,
         }
     }
 }
 
 impl Matcher for FuzzyMatcher {
-    fn match_term(&self, input: &[u8], opts: MatchOptions) -> Option<MatchResult> {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
         let term = &self.term;
-        if term.is_empty() || input.is_empty() {
+        let haystack = input.chars();
+        if term.is_empty() || haystack.is_empty() || !could_match_subsequence(term, haystack, config) {
             return None;
         }
 
-        let mut state = InputState::Beginning;
-        
+        let mut state: Option<CharClass> = None;
+
         let mut total_score = 0;
-        let mut matches = if opts.match_position {
+        let mut matches = if config.match_position {
             vec![0; term.len()]
         } else {
             Vec::new()
         };
-        
+
         let mut term_chars = term.iter().copied();
-        
+        let mut term_chars_raw = self.term_raw.iter().copied();
+
         let mut term_index = 0;
         let mut current = term_chars.next().unwrap();
-        
+        let mut current_raw = term_chars_raw.next().unwrap();
+
         let mut last_match = 0;
-        let mut char_index = 0;
-        let mut byte_index = 0;
-        while byte_index < input.len() {
-            if let Some(c) = next_code_point(&input[byte_index..input.len()]) {
-                if c == current {
-                    let distance = choose!(term_index != 0, char_index - last_match, 0);
-                    total_score += bonus_at(state, c, distance);
-                    last_match = char_index;
-                    if opts.match_position {
-                        matches[term_index] = byte_index;
-                    }
-                    if let Some(ch) = term_chars.next() {
-                        current = ch;
-                        term_index += 1;
+        for (char_index, &raw) in haystack.iter().enumerate() {
+            let c = normalize(raw, config);
+            if c == current {
+                let distance = choose!(term_index != 0, char_index - last_match, 0);
+                total_score += bonus_at(state, raw, current_raw, distance, config);
+                last_match = char_index;
+                if config.match_position {
+                    matches[term_index] = char_index;
+                }
+                if let Some(ch) = term_chars.next() {
+                    current = ch;
+                    current_raw = term_chars_raw.next().unwrap();
+                    term_index += 1;
+                } else {
+                    return Some(MatchResult {
+                        score: total_score,
+                        matches: if config.match_position {
+                            Some(matches)
+                        } else {
+                            None
+                        }
+                    });
+                }
+            }
+            state = Some(char_class(raw, config));
+        }
+
+        None
+    }
+
+    fn could_match(&self, input: &Utf32Input, config: &MatcherConfig) -> bool {
+        could_match_subsequence(&self.term, input.chars(), config)
+    }
+}
+
+/// Scores a term against the haystack with a Smith-Waterman-style dynamic program,
+/// rather than greedily locking in the first occurrence of each pattern char like
+/// `FuzzyMatcher` does. This lets it prefer, e.g., a later consecutive run over an
+/// earlier scattered one. Each DP cell keeps a single score, so a match that only
+/// pays off through a later consecutive bonus can lose to a higher gap score at that
+/// cell; the result is then not the highest-scoring alignment (e.g. "ab" against
+/// "x-aab" scores 13 via positions 2 and 4, while positions 3 and 4 would score 15).
+#[derive(Debug)]
+pub struct OptimalFuzzyMatcher {
+    term: Vec<char>,
+}
+
+impl OptimalFuzzyMatcher {
+    pub fn new(term: &str, config: &MatcherConfig) -> Self {
+        OptimalFuzzyMatcher {
+            term: term.chars().map(|c| normalize(c, config)).collect(),
+        }
+    }
+}
+
+impl Matcher for OptimalFuzzyMatcher {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
+        let term = &self.term;
+        let raw_haystack = input.chars();
+        if term.is_empty() || raw_haystack.is_empty() || !could_match_subsequence(term, raw_haystack, config) {
+            return None;
+        }
+
+        // Normalized chars and per-position bonuses are precomputed up front so the
+        // DP below can index into them randomly. Bonuses are computed per haystack
+        // position independent of which pattern char ends up aligned there, so
+        // unlike `FuzzyMatcher`/`ExactMatcher` this doesn't apply `case_mismatch_penalty`.
+        let mut haystack = Vec::with_capacity(raw_haystack.len());
+        let mut bonus = Vec::with_capacity(raw_haystack.len());
+        let mut state: Option<CharClass> = None;
+        for &c in raw_haystack {
+            bonus.push(bonus_at(state, c, c, 0, config));
+            haystack.push(normalize(c, config));
+            state = Some(char_class(c, config));
+        }
+
+        let m = term.len();
+        let n = haystack.len();
+        if n < m {
+            return None;
+        }
+
+        // H[i][j] is the score kept for matching pattern[0..=i] within haystack[0..=j]:
+        // either pattern[i] consumes haystack[j], or H[i][j-1] is carried over minus a
+        // gap penalty, whichever is higher. CONSECUTIVE[i][j] records whether the kept
+        // value is the one where pattern[i] consumed haystack[j], so the next row can
+        // chain a consecutive bonus off it and backtracking knows where each pattern
+        // char landed. GAP_LEN[i][j] is the length of the run of skipped haystack chars
+        // leading into H[i][j], for progressive gap penalties.
+        let mut h = vec![0isize; m * n];
+        let mut consecutive = vec![false; m * n];
+        let mut gap_len = vec![0usize; m * n];
+
+        for i in 0..m {
+            for j in 0..n {
+                let idx = i * n + j;
+                if term[i] == haystack[j] {
+                    let diag = if i == 0 {
+                        Some(0)
+                    } else if j == 0 || h[(i - 1) * n + (j - 1)] <= UNREACHABLE {
+                        None
                     } else {
-                        return Some(MatchResult {
-                            score: total_score,
-                            matches: if opts.match_position {
-                                Some(matches)
-                            } else {
-                                None
-                            }
-                        });
+                        Some(h[(i - 1) * n + (j - 1)])
+                    };
+                    let diag_score = diag.map(|score| {
+                        let chained = i > 0 && j > 0 && consecutive[(i - 1) * n + (j - 1)];
+                        score + config.score_match + bonus[j] + choose!(chained, config.score_consecutive, 0)
+                    });
+                    let gap_score = if j == 0 || h[idx - 1] <= UNREACHABLE {
+                        None
+                    } else {
+                        Some(h[idx - 1] - gap_penalty(gap_len[idx - 1] + 1, config))
+                    };
+                    match (diag_score, gap_score) {
+                        (Some(d), Some(g)) if g > d => {
+                            h[idx] = g;
+                            consecutive[idx] = false;
+                            gap_len[idx] = gap_len[idx - 1] + 1;
+                        }
+                        (Some(d), _) => {
+                            h[idx] = d;
+                            consecutive[idx] = true;
+                            gap_len[idx] = 0;
+                        }
+                        (None, Some(g)) => {
+                            h[idx] = g;
+                            consecutive[idx] = false;
+                            gap_len[idx] = gap_len[idx - 1] + 1;
+                        }
+                        (None, None) => {
+                            h[idx] = UNREACHABLE;
+                            consecutive[idx] = false;
+                            gap_len[idx] = 0;
+                        }
                     }
+                } else if j == 0 || h[idx - 1] <= UNREACHABLE {
+                    h[idx] = UNREACHABLE;
+                    consecutive[idx] = false;
+                    gap_len[idx] = 0;
+                } else {
+                    gap_len[idx] = gap_len[idx - 1] + 1;
+                    h[idx] = h[idx - 1] - gap_penalty(gap_len[idx], config);
+                    consecutive[idx] = false;
                 }
-                state = state_from_char(c);
-                char_index += 1;
-                byte_index += c.len_utf8();
+            }
+        }
+
+        let last_row = (m - 1) * n;
+        let (best_j, best_score) = (0..n)
+            .map(|j| (j, h[last_row + j]))
+            .max_by_key(|&(_, score)| score)?;
+        if best_score <= UNREACHABLE {
+            return None;
+        }
+
+        let mut positions = vec![0usize; m];
+        let mut i = m - 1;
+        let mut j = best_j;
+        loop {
+            let idx = i * n + j;
+            if consecutive[idx] {
+                positions[i] = j;
+                if i == 0 {
+                    break;
+                }
+                i -= 1;
+                j -= 1;
             } else {
-                char_index += 1;
-                byte_index += 1;
+                j -= 1;
             }
         }
 
-        return None;
+        Some(MatchResult {
+            score: best_score,
+            matches: if config.match_position {
+                Some(positions)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn could_match(&self, input: &Utf32Input, config: &MatcherConfig) -> bool {
+        could_match_subsequence(&self.term, input.chars(), config)
     }
 }
 
 #[derive(Debug)]
 pub struct ExactMatcher {
     term: Vec<char>,
+    term_raw: Vec<char>,
     failure_function: Vec<isize>
 }
 
 impl ExactMatcher {
-    pub fn new(term: &str) -> Self {
-        let chars: Vec<char> = term.chars().collect();
+    pub fn new(term: &str, config: &MatcherConfig) -> Self {
+        let term_raw: Vec<char> = term.chars().collect();
+        let chars: Vec<char> = term_raw.iter().map(|&c| normalize(c, config)).collect();
         let failure_function = build_failure_function(&chars);
         ExactMatcher {
             term: chars,
+            term_raw,
             failure_function
         }
     }
 }
 
 impl Matcher for ExactMatcher {
-    fn match_term(&self, input: &[u8], opts: MatchOptions) -> Option<MatchResult> {
-        let mut state = InputState::Beginning;
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
+        let haystack = input.chars();
+        let mut state: Option<CharClass> = None;
         let mut i = 0;
         let mut j = 0;
 
@@ -162,43 +422,39 @@ impl Matcher for ExactMatcher {
         let mut best_start = 0;
         let mut best_score = 0;
 
-        while i < input.len() {
-            if let Some(c) = next_code_point(&input[i..input.len()]) {
-                if c == self.term[j] {
-                    if j == 0 {
-                        match_start = i;
-                        match_score = bonus_at(state, c, 0);
+        while i < haystack.len() {
+            let raw = haystack[i];
+            let c = normalize(raw, config);
+            if c == self.term[j] {
+                if j == 0 {
+                    match_start = i;
+                    match_score = bonus_at(state, raw, self.term_raw[j], 0, config);
+                }
+                i += 1;
+                j += 1;
+                if j == self.term.len() {
+                    if match_score > best_score {
+                        best_start = match_start;
+                        best_score = match_score;
                     }
+                    j = self.failure_function[self.term.len()] as usize;
+                }
+            } else {
+                if self.failure_function[j] < 0 {
                     i += 1;
-                    j += 1;
-                    if j == self.term.len() {
-                        if match_score > best_score {
-                            best_start = match_start;
-                            best_score = match_score;
-                        }
-                        j = self.failure_function[self.term.len()] as usize;
-                    }
+                    j = 0;
                 } else {
-                    if self.failure_function[j] < 0 {
-                        i += 1;
-                        j = 0;
-                    } else {
-                        j = self.failure_function[j] as usize;
-                    }
-                    if j == 0 {
-                        match_score = 0;
-                    }
+                    j = self.failure_function[j] as usize;
+                }
+                if j == 0 {
+                    match_score = 0;
                 }
-                state = state_from_char(c);
-            } else {
-                i += 1;
-                j = 0;
-                match_score = 0;
             }
+            state = Some(char_class(raw, config));
         }
         if best_score > 0 {
-            let matches: Option<Vec<usize>> = if opts.match_position {
-                let best_end = best_start + self.term.len(); 
+            let matches: Option<Vec<usize>> = if config.match_position {
+                let best_end = best_start + self.term.len();
                 Some((best_start..best_end).collect())
             } else {
                 None
@@ -212,7 +468,10 @@ impl Matcher for ExactMatcher {
 
 fn build_failure_function(term: &[char]) -> Vec<isize> {
     let mut table: Vec<isize> = vec![-1; term.len() + 1];
-        
+    if term.is_empty() {
+        return table;
+    }
+
     let mut pos = 1;
     let mut cnd = 0isize;
 
@@ -220,7 +479,7 @@ fn build_failure_function(term: &[char]) -> Vec<isize> {
 
     while pos < term.len() {
         if term[pos] == term[cnd as usize] {
-            table[pos] = table[cnd as usize];    
+            table[pos] = table[cnd as usize];
         } else {
             table[pos] = cnd as isize;
             cnd = table[cnd as usize];
@@ -264,48 +523,160 @@ mod tests {
         }};
     }
 
-    fn run_fuzzy_match(term: &str, input: &[u8], opts: MatchOptions) -> Option<MatchResult> {
-        FuzzyMatcher::new(term).match_term(input, opts)
+    fn run_fuzzy_match(term: &str, input: &[u8], config: &MatcherConfig) -> Option<MatchResult> {
+        FuzzyMatcher::new(term, config).match_term(&Utf32Input::new(input), config)
+    }
+
+    fn run_exact_match(term: &str, input: &[u8], config: &MatcherConfig) -> Option<MatchResult> {
+        ExactMatcher::new(term, config).match_term(&Utf32Input::new(input), config)
+    }
+
+    fn run_optimal_match(term: &str, input: &[u8], config: &MatcherConfig) -> Option<MatchResult> {
+        OptimalFuzzyMatcher::new(term, config).match_term(&Utf32Input::new(input), config)
+    }
+
+    fn opts_default() -> MatcherConfig {
+        MatcherConfig::default()
+    }
+
+    fn opts_position() -> MatcherConfig {
+        MatcherConfig { match_position: true, ..MatcherConfig::default() }
+    }
+
+    fn opts_case_sensitive() -> MatcherConfig {
+        MatcherConfig { case_sensitive: true, ..MatcherConfig::default() }
     }
 
-    fn run_exact_match(term: &str, input: &[u8], opts: MatchOptions) -> Option<MatchResult> {
-        ExactMatcher::new(term).match_term(input, opts)
+    fn opts_normalize_unicode() -> MatcherConfig {
+        MatcherConfig { normalize_unicode: true, ..MatcherConfig::default() }
     }
 
-    static OPTS_DEFAULT: MatchOptions = MatchOptions { case_sensitive: false, match_position: false };
-    static OPTS_POSITION: MatchOptions = MatchOptions { case_sensitive: false, match_position: true };
+    const SCORE_BEGINNING: isize = 20;
+    const SCORE_BOUNDARY: isize = 10;
+    const SCORE_MATCH: isize = 3;
+    const SCORE_CONSECUTIVE: isize = 3;
 
     #[test]
     fn fuzzy_matcher() {
-        assert_match_success!(run_fuzzy_match("ABC", b"ADDD BDDD CDDD", OPTS_DEFAULT), SCORE_BEGINNING + 2 * SCORE_BOUNDARY);
-        assert_match_success!(run_fuzzy_match("ABC", b"ABC", OPTS_DEFAULT),SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE);
-        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BDDD CDDD", OPTS_DEFAULT), 3 * SCORE_BOUNDARY);
-        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BCDDD CDDD ABC", OPTS_DEFAULT), 2 * SCORE_BOUNDARY + SCORE_MATCH + SCORE_CONSECUTIVE);
-        assert_match_success!(run_fuzzy_match("ABC", b"AB\xd8\x3fC", OPTS_DEFAULT), SCORE_BEGINNING + 2 * SCORE_MATCH + SCORE_CONSECUTIVE);
-        
-        assert_match_success!(run_fuzzy_match("ABC", b"ADDD BDDD CDDD", OPTS_POSITION), SCORE_BEGINNING + 2 * SCORE_BOUNDARY, [0,5,10]);
-        assert_match_success!(run_fuzzy_match("ABC", b"ABC", OPTS_POSITION),SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE, [0,1,2]);
-        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BDDD CDDD", OPTS_POSITION), 3 * SCORE_BOUNDARY, [4,9,14]);
-        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BCDDD CDDD ABC", OPTS_POSITION), 2 * SCORE_BOUNDARY + SCORE_MATCH + SCORE_CONSECUTIVE, [4,9,10]);
-        assert_match_success!(run_fuzzy_match("ABC", b"AB\xd8\x3fC", OPTS_POSITION), SCORE_BEGINNING + 2 * SCORE_MATCH + SCORE_CONSECUTIVE, [0,1,4]);
-        
-        assert_match_failure!(run_fuzzy_match("ABC", b"AB", OPTS_DEFAULT));
-        assert_match_failure!(run_fuzzy_match("ABC", b"DDD AB", OPTS_DEFAULT));
-        assert_match_failure!(run_fuzzy_match("ABC", b"DDD ADDD BDDD", OPTS_DEFAULT));
+        let default = opts_default();
+        let position = opts_position();
+        assert_match_success!(run_fuzzy_match("ABC", b"ADDD BDDD CDDD", &default), SCORE_BEGINNING + 2 * SCORE_BOUNDARY);
+        assert_match_success!(run_fuzzy_match("ABC", b"ABC", &default),SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE);
+        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BDDD CDDD", &default), 3 * SCORE_BOUNDARY);
+        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BCDDD CDDD ABC", &default), 2 * SCORE_BOUNDARY + SCORE_MATCH + SCORE_CONSECUTIVE);
+        assert_match_success!(run_fuzzy_match("ABC", b"AB\xd8\x3fC", &default), SCORE_BEGINNING + 2 * SCORE_MATCH + SCORE_CONSECUTIVE);
+
+        assert_match_success!(run_fuzzy_match("ABC", b"ADDD BDDD CDDD", &position), SCORE_BEGINNING + 2 * SCORE_BOUNDARY, [0,5,10]);
+        assert_match_success!(run_fuzzy_match("ABC", b"ABC", &position),SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE, [0,1,2]);
+        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BDDD CDDD", &position), 3 * SCORE_BOUNDARY, [4,9,14]);
+        assert_match_success!(run_fuzzy_match("ABC", b"DDD ADDD BCDDD CDDD ABC", &position), 2 * SCORE_BOUNDARY + SCORE_MATCH + SCORE_CONSECUTIVE, [4,9,10]);
+        assert_match_success!(run_fuzzy_match("ABC", b"AB\xd8\x3fC", &position), SCORE_BEGINNING + 2 * SCORE_MATCH + SCORE_CONSECUTIVE, [0,1,3]);
+
+        assert_match_failure!(run_fuzzy_match("ABC", b"AB", &default));
+        assert_match_failure!(run_fuzzy_match("ABC", b"DDD AB", &default));
+        assert_match_failure!(run_fuzzy_match("ABC", b"DDD ADDD BDDD", &default));
+    }
+
+    #[test]
+    fn exact_matcher() {
+        let default = opts_default();
+        let position = opts_position();
+        assert_match_success!(run_exact_match("ABC", b"ABC", &default), SCORE_BEGINNING);
+        assert_match_success!(run_exact_match("ABC", b"DDDABC", &default), SCORE_MATCH);
+        assert_match_success!(run_exact_match("ABC", b"DDDABC ABC", &default), SCORE_BOUNDARY);
+
+        assert_match_success!(run_exact_match("ABC", b"ABC", &position), SCORE_BEGINNING, [0,1,2]);
+        assert_match_success!(run_exact_match("ABC", b"DDDABC", &position), SCORE_MATCH, [3,4,5]);
+        assert_match_success!(run_exact_match("ABC", b"DDDABC ABC", &position), SCORE_BOUNDARY, [7,8,9]);
+
+        assert_match_failure!(run_exact_match("ABC", b"AB", &default));
+        assert_match_failure!(run_exact_match("ABC", b"AB\xd8\x3fC", &default));
+        assert_match_failure!(run_exact_match("ABC", b"ABDC", &default));
+    }
+
+    #[test]
+    fn optimal_fuzzy_matcher() {
+        let default = opts_default();
+        let position = opts_position();
+        assert_match_success!(run_optimal_match("ABC", b"ABC", &default), SCORE_BEGINNING + 5 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE);
+        assert_match_success!(run_optimal_match("ABC", b"ABC", &position), SCORE_BEGINNING + 5 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE, [0,1,2]);
+
+        // A later consecutive run ("ab" at the end) outscores an earlier scattered
+        // match ("a" at the start, "b" at the end) that the greedy FuzzyMatcher would
+        // have locked in instead.
+        assert_match_success!(run_optimal_match("ab", b"a.ab", &position), 3 * SCORE_MATCH + SCORE_BOUNDARY + SCORE_CONSECUTIVE, [2,3]);
+
+        assert_match_failure!(run_optimal_match("ABC", b"AB", &default));
+        assert_match_failure!(run_optimal_match("ABC", b"DDD AB", &default));
     }
 
     #[test]
-    fn exact_matcher() {   
-        assert_match_success!(run_exact_match("ABC", b"ABC", OPTS_DEFAULT), SCORE_BEGINNING);
-        assert_match_success!(run_exact_match("ABC", b"DDDABC", OPTS_DEFAULT), SCORE_MATCH);
-        assert_match_success!(run_exact_match("ABC", b"DDDABC ABC", OPTS_DEFAULT), SCORE_BOUNDARY);
-        
-        assert_match_success!(run_exact_match("ABC", b"ABC", OPTS_POSITION), SCORE_BEGINNING, [0,1,2]);
-        assert_match_success!(run_exact_match("ABC", b"DDDABC", OPTS_POSITION), SCORE_MATCH, [3,4,5]);
-        assert_match_success!(run_exact_match("ABC", b"DDDABC ABC", OPTS_POSITION), SCORE_BOUNDARY, [7,8,9]);
-
-        assert_match_failure!(run_exact_match("ABC", b"AB", OPTS_DEFAULT));
-        assert_match_failure!(run_exact_match("ABC", b"AB\xd8\x3fC", OPTS_DEFAULT));
-        assert_match_failure!(run_exact_match("ABC", b"ABDC", OPTS_DEFAULT));
-    }
-}
\ No newline at end of file
+    fn case_folding() {
+        let default = opts_default();
+        let case_sensitive = opts_case_sensitive();
+        assert_match_success!(run_fuzzy_match("abc", b"ABC", &default), SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE);
+        assert_match_success!(run_exact_match("abc", b"ABC", &default), SCORE_BEGINNING);
+
+        assert_match_failure!(run_fuzzy_match("abc", b"ABC", &case_sensitive));
+        assert_match_failure!(run_exact_match("abc", b"ABC", &case_sensitive));
+    }
+
+    #[test]
+    fn could_match_prefilter() {
+        let default = opts_default();
+        let fuzzy = FuzzyMatcher::new("abc", &default);
+        assert!(fuzzy.could_match(&Utf32Input::new(b"xAxBxC"), &default));
+        assert!(!fuzzy.could_match(&Utf32Input::new(b"xAxBx"), &default));
+        assert!(!fuzzy.could_match(&Utf32Input::new(b"cba"), &default));
+
+        let optimal = OptimalFuzzyMatcher::new("abc", &default);
+        assert!(optimal.could_match(&Utf32Input::new(b"xAxBxC"), &default));
+        assert!(!optimal.could_match(&Utf32Input::new(b"xAxBx"), &default));
+    }
+
+    #[test]
+    fn camel_case_boundaries() {
+        let default = opts_default();
+        // "B" starts a new word inside "FooBar" (lower -> upper transition), so it
+        // scores a boundary bonus even with no delimiter or space before it.
+        assert_match_success!(run_fuzzy_match("fb", b"FooBar", &default), SCORE_BEGINNING + SCORE_BOUNDARY);
+        // Same positions without the case transition score as plain matches instead.
+        assert_match_success!(run_fuzzy_match("fb", b"foobar", &default), SCORE_BEGINNING + SCORE_MATCH);
+    }
+
+    #[test]
+    fn caseless_letters_count_as_word_chars() {
+        let default = opts_default();
+        // A caseless letter (here, decoded from a malformed byte sequence, but the
+        // same class as a CJK/Arabic/Hebrew character) sits between "B" and "C" and
+        // should score like an ordinary word->word match, not a boundary: it has no
+        // case to transition from, so it must not trip the lower->upper camelCase
+        // bonus the way a real lowercase letter would.
+        assert_match_success!(run_fuzzy_match("ABC", b"AB\xd8\x3fC", &default), SCORE_BEGINNING + 2 * SCORE_MATCH + SCORE_CONSECUTIVE);
+    }
+
+    #[test]
+    fn unicode_normalization() {
+        let normalize_unicode = opts_normalize_unicode();
+        let default = opts_default();
+        assert_match_success!(run_exact_match("cafe", "café".as_bytes(), &normalize_unicode), SCORE_BEGINNING);
+        assert_match_failure!(run_exact_match("cafe", "café".as_bytes(), &default));
+
+        assert_match_success!(run_fuzzy_match("CAFE", "café".as_bytes(), &normalize_unicode), SCORE_BEGINNING + 3 * SCORE_MATCH + 3 * SCORE_CONSECUTIVE);
+    }
+
+    #[test]
+    fn configurable_weights() {
+        let config = MatcherConfig { score_match: 100, ..MatcherConfig::default() };
+        assert_match_success!(run_fuzzy_match("ABC", b"ABC", &config), config.score_beginning + 2 * config.score_match + 2 * config.score_consecutive);
+    }
+
+    #[test]
+    fn case_mismatch_penalty() {
+        let config = MatcherConfig { case_mismatch_penalty: 5, ..MatcherConfig::default() };
+        // "abc" matching "ABC" needs case folding on every char, so each one eats the penalty.
+        assert_match_success!(run_fuzzy_match("abc", b"ABC", &config), SCORE_BEGINNING - 5 + 2 * (SCORE_MATCH - 5) + 2 * SCORE_CONSECUTIVE);
+        // An exact-case match never folds, so the penalty never applies.
+        assert_match_success!(run_fuzzy_match("ABC", b"ABC", &config), SCORE_BEGINNING + 2 * SCORE_MATCH + 2 * SCORE_CONSECUTIVE);
+    }
+}
@@ -1,46 +1,257 @@
-use super::matcher::{Matcher, MatchResult, MatchOptions, FuzzyMatcher, ExactMatcher};
+use super::config::MatcherConfig;
+use super::matcher::{Matcher, MatchResult, FuzzyMatcher, OptimalFuzzyMatcher, ExactMatcher};
+use super::unicode::Utf32Input;
+
+enum Anchor {
+    Start,
+    End,
+    Both,
+}
+
+/// Anchors a term to a boundary of the input. Built on top of `ExactMatcher`
+/// (forcing `match_position` on internally) and then checking that its best match
+/// actually sits at the requested boundary, rather than anywhere in the input.
+struct BoundaryMatcher {
+    inner: ExactMatcher,
+    anchor: Anchor,
+}
+
+impl Matcher for BoundaryMatcher {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
+        let probe_config = MatcherConfig { match_position: true, ..config.clone() };
+        let result = self.inner.match_term(input, &probe_config)?;
+        let positions = result.matches()?;
+        let at_boundary = match self.anchor {
+            Anchor::Start => positions.first() == Some(&0),
+            Anchor::End => positions.last().map(|&p| p + 1) == Some(input.len()),
+            Anchor::Both => positions.first() == Some(&0) && positions.last().map(|&p| p + 1) == Some(input.len()),
+        };
+        if !at_boundary {
+            return None;
+        }
+        Some(MatchResult::new(result.score(), if config.match_position {
+            Some(positions.to_vec())
+        } else {
+            None
+        }))
+    }
+}
+
+/// Rejects the whole candidate if its inner term matches; otherwise contributes no
+/// score, so `!foo` simply removes matches from consideration rather than ranking
+/// them.
+struct InverseMatcher {
+    inner: Box<TermMatcher>,
+}
+
+impl Matcher for InverseMatcher {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
+        match self.inner.match_term(input, config) {
+            Some(_) => None,
+            None => Some(MatchResult::new(0, None)),
+        }
+    }
+}
+
+/// Satisfied when any of its alternatives match; takes the best-scoring one.
+struct OrMatcher {
+    alternatives: Vec<TermMatcher>,
+}
+
+impl Matcher for OrMatcher {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
+        self.alternatives.iter()
+            .filter_map(|m| m.match_term(input, config))
+            .max_by_key(|result| result.score())
+    }
+
+    fn could_match(&self, input: &Utf32Input, config: &MatcherConfig) -> bool {
+        self.alternatives.iter().any(|m| m.could_match(input, config))
+    }
+}
 
 enum TermMatcher {
     Fuzzy(FuzzyMatcher),
+    Optimal(OptimalFuzzyMatcher),
     Exact(ExactMatcher),
+    Boundary(BoundaryMatcher),
+    Inverse(InverseMatcher),
+    Or(OrMatcher),
 }
 
 impl Matcher for TermMatcher {
-    fn match_term(&self, input: &[u8], opts: MatchOptions) -> Option<MatchResult> {
+    fn match_term(&self, input: &Utf32Input, config: &MatcherConfig) -> Option<MatchResult> {
         match self {
-            TermMatcher::Fuzzy(m) => m.match_term(input, opts), 
-            TermMatcher::Exact(m) => m.match_term(input, opts), 
+            TermMatcher::Fuzzy(m) => m.match_term(input, config),
+            TermMatcher::Optimal(m) => m.match_term(input, config),
+            TermMatcher::Exact(m) => m.match_term(input, config),
+            TermMatcher::Boundary(m) => m.match_term(input, config),
+            TermMatcher::Inverse(m) => m.match_term(input, config),
+            TermMatcher::Or(m) => m.match_term(input, config),
+        }
+    }
+
+    fn could_match(&self, input: &Utf32Input, config: &MatcherConfig) -> bool {
+        match self {
+            TermMatcher::Fuzzy(m) => m.could_match(input, config),
+            TermMatcher::Optimal(m) => m.could_match(input, config),
+            TermMatcher::Exact(m) => m.could_match(input, config),
+            TermMatcher::Boundary(m) => m.could_match(input, config),
+            TermMatcher::Inverse(m) => m.could_match(input, config),
+            TermMatcher::Or(m) => m.could_match(input, config),
         }
     }
 }
 
 pub struct Pattern {
     terms: Vec<TermMatcher>,
-    opts: MatchOptions,
+    config: MatcherConfig,
 }
 
 impl Pattern {
-    pub fn new(input: &str, opts: MatchOptions) -> Pattern {
+    pub fn new(input: &str, config: MatcherConfig) -> Pattern {
         Pattern {
-            opts,
-            terms: parse_terms(input),
+            terms: parse_terms(input, &config),
+            config,
         }
     }
 
     pub fn matches(&self, input: &[u8]) -> Option<Vec<MatchResult>> {
-        self.terms.iter().map(|m| m.match_term(input, self.opts)).collect()
+        self.matches_decoded(&Utf32Input::new(input))
+    }
+
+    /// Cheap check that every term could plausibly match `input`, so callers
+    /// scanning large candidate sets (e.g. a directory walk) can skip the
+    /// allocation-heavy `matches` call for obvious non-matches.
+    pub fn could_match(&self, input: &[u8]) -> bool {
+        self.could_match_decoded(&Utf32Input::new(input))
     }
+
+    /// Same as `matches`, but takes an already-decoded `input` so a caller checking
+    /// both `could_match_decoded` and `matches_decoded` on the same candidate (or
+    /// scoring it against several patterns) only pays for the UTF-8 decode once.
+    pub fn matches_decoded(&self, input: &Utf32Input) -> Option<Vec<MatchResult>> {
+        self.terms.iter().map(|m| m.match_term(input, &self.config)).collect()
+    }
+
+    /// Same as `could_match`, but takes an already-decoded `input`.
+    pub fn could_match_decoded(&self, input: &Utf32Input) -> bool {
+        self.terms.iter().all(|m| m.could_match(input, &self.config))
+    }
+}
+
+/// Parses a single whitespace-delimited token, which may itself be an `|`-separated
+/// OR group (e.g. `^foo|bar$|!baz`). Each side of a `|` is parsed as its own term via
+/// `parse_single_term`.
+fn parse_term(token: &str, config: &MatcherConfig) -> TermMatcher {
+    if token.contains('|') {
+        let alternatives = token.split('|')
+            .filter(|part| !part.is_empty())
+            .map(|part| parse_single_term(part, config))
+            .collect();
+        return TermMatcher::Or(OrMatcher { alternatives });
+    }
+    parse_single_term(token, config)
 }
 
-fn parse_term(term: &str) -> TermMatcher {
+fn parse_single_term(term: &str, config: &MatcherConfig) -> TermMatcher {
+    if term.is_empty() {
+        return literal_term(term, config);
+    }
+    if let Some(rest) = term.strip_prefix('!') {
+        if rest.is_empty() {
+            return literal_term(term, config);
+        }
+        return TermMatcher::Inverse(InverseMatcher { inner: Box::new(parse_single_term(rest, config)) });
+    }
+    // An operand of length zero (e.g. `"^"` or a bare `"'"`) would hand
+    // `ExactMatcher` an empty term, which it can't represent internally, so those
+    // are treated as literal text rather than dispatched as operators. `"^$"` has
+    // no room for both anchors and is read as a start-anchored `"$"`.
+    let is_prefix = term.starts_with('^') && term.len() > 1;
+    let is_suffix = term.len() > 1 && term.ends_with('$');
     match term.as_bytes().first().unwrap() {
-        39 => TermMatcher::Exact(ExactMatcher::new(&term[1..term.len()])),
-        _  => TermMatcher::Fuzzy(FuzzyMatcher::new(term)),
+        39 if term.len() > 1 => TermMatcher::Exact(ExactMatcher::new(&term[1..], config)),
+        b'^' if is_prefix && is_suffix && term.len() > 2 => TermMatcher::Boundary(BoundaryMatcher {
+            inner: ExactMatcher::new(&term[1..term.len() - 1], config),
+            anchor: Anchor::Both,
+        }),
+        b'^' if is_prefix => TermMatcher::Boundary(BoundaryMatcher {
+            inner: ExactMatcher::new(&term[1..], config),
+            anchor: Anchor::Start,
+        }),
+        _ if is_suffix => TermMatcher::Boundary(BoundaryMatcher {
+            inner: ExactMatcher::new(&term[..term.len() - 1], config),
+            anchor: Anchor::End,
+        }),
+        _ => literal_term(term, config),
     }
 }
 
-fn parse_terms(input: &str) -> Vec<TermMatcher> {
+/// Falls back to the plain fuzzy/optimal matcher for a term that isn't (or no
+/// longer, once a degenerate empty operand is stripped away) an operator.
+fn literal_term(term: &str, config: &MatcherConfig) -> TermMatcher {
+    if config.optimal {
+        TermMatcher::Optimal(OptimalFuzzyMatcher::new(term, config))
+    } else {
+        TermMatcher::Fuzzy(FuzzyMatcher::new(term, config))
+    }
+}
+
+fn parse_terms(input: &str, config: &MatcherConfig) -> Vec<TermMatcher> {
     input.split_whitespace()
-        .map(|t| parse_term(t))
+        .map(|t| parse_term(t, config))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, input: &str) -> bool {
+        Pattern::new(query, MatcherConfig::default()).matches(input.as_bytes()).is_some()
+    }
+
+    #[test]
+    fn anchored_terms() {
+        assert!(matches("^foo", "foobar"));
+        assert!(!matches("^foo", "barfoo"));
+
+        assert!(matches("bar$", "foobar"));
+        assert!(!matches("bar$", "barfoo"));
+
+        assert!(matches("^foobar$", "foobar"));
+        assert!(!matches("^foobar$", "foobarbaz"));
+    }
+
+    #[test]
+    fn inverse_terms() {
+        assert!(matches("!bar", "foo"));
+        assert!(!matches("!bar", "foobar"));
+        assert!(matches("foo !bar", "foobaz"));
+        assert!(!matches("foo !bar", "foobar"));
+    }
+
+    #[test]
+    fn degenerate_operator_terms_dont_panic() {
+        // A bare operator with no operand (e.g. typed mid-query, one keystroke at a
+        // time) falls back to matching itself literally instead of panicking. `^$`
+        // has no room for both anchors, so it is read as `$` anchored to the start.
+        assert!(matches("!", "foo!bar"));
+        assert!(!matches("!", "foobar"));
+        assert!(matches("^", "foo^bar"));
+        assert!(matches("^$", "$foobar"));
+        assert!(!matches("^$", "foo$bar"));
+        assert!(matches("'", "foo'bar"));
+    }
+
+    #[test]
+    fn or_terms() {
+        assert!(matches("foo|bar", "somebar"));
+        assert!(matches("foo|bar", "somefoo"));
+        assert!(!matches("foo|bar", "somebaz"));
+        assert!(matches("^foo|bar$", "foobaz"));
+        assert!(matches("^foo|bar$", "bazbar"));
+        assert!(!matches("^foo|bar$", "bazfoo"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam::channel::{self, Receiver, Sender};
+use rayon::prelude::*;
+
+use super::matcher::MatchResult;
+use super::pattern::Pattern;
+use super::unicode::Utf32Input;
+
+/// A candidate that matched a `Pattern`, carrying its total score: the sum of the
+/// `MatchResult::score` of every term in the pattern.
+#[derive(Debug, Clone)]
+pub struct ScoredMatch {
+    pub candidate: String,
+    pub score: isize,
+}
+
+fn total_score(pattern: &Pattern, input: &Utf32Input) -> Option<isize> {
+    if !pattern.could_match_decoded(input) {
+        return None;
+    }
+    pattern.matches_decoded(input).map(|results| results.iter().map(MatchResult::score).sum())
+}
+
+/// Scores `candidates` against `pattern` in parallel and returns the `limit`
+/// best-scoring matches, sorted by descending total score. Ties break in favor of
+/// the shorter candidate, with a stable order for anything still tied after that.
+/// Each candidate is decoded into a `Utf32Input` once and shared across every term
+/// of `pattern`, rather than re-walking its UTF-8 bytes per term. Candidates are
+/// borrowed rather than consumed, so callers re-scoring the same set against
+/// successive queries (e.g. `IncrementalSearcher`) don't need to clone it upfront;
+/// only the ones that actually score are cloned, into the returned `ScoredMatch`es.
+pub fn search(pattern: &Pattern, candidates: &[String], limit: usize) -> Vec<ScoredMatch> {
+    let mut scored: Vec<ScoredMatch> = candidates
+        .par_iter()
+        .filter_map(|candidate| {
+            let input = Utf32Input::new(candidate.as_bytes());
+            let score = total_score(pattern, &input)?;
+            Some(ScoredMatch { candidate: candidate.clone(), score })
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.candidate.len().cmp(&b.candidate.len())));
+    scored.truncate(limit);
+    scored
+}
+
+enum Command {
+    Query(String),
+    Shutdown,
+}
+
+/// Re-ranks a fixed candidate set against a query that changes over time (e.g. as a
+/// user types), without re-reading the candidates on every keystroke. Scoring runs
+/// on a dedicated worker thread so the caller's thread (typically a UI event loop)
+/// never blocks on it; queries and results cross `crossbeam::channel`s.
+pub struct IncrementalSearcher {
+    commands: Sender<Command>,
+    results: Receiver<Vec<ScoredMatch>>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl IncrementalSearcher {
+    /// Spawns the worker thread, handing it ownership of `candidates` and the
+    /// `Pattern` factory's config via `make_pattern`. `make_pattern` is called once
+    /// per `set_query`, so it's typically `|query| Pattern::new(query, config.clone())`.
+    pub fn spawn<F>(candidates: Vec<String>, limit: usize, make_pattern: F) -> Self
+    where
+        F: Fn(&str) -> Pattern + Send + 'static,
+    {
+        let candidates = Arc::new(candidates);
+        let (command_tx, command_rx) = channel::unbounded::<Command>();
+        let (result_tx, result_rx) = channel::unbounded::<Vec<ScoredMatch>>();
+
+        let worker = thread::spawn(move || {
+            for command in command_rx.iter() {
+                let query = match command {
+                    Command::Query(query) => query,
+                    Command::Shutdown => break,
+                };
+                let pattern = make_pattern(&query);
+                let results = search(&pattern, &candidates, limit);
+                if result_tx.send(results).is_err() {
+                    break;
+                }
+            }
+        });
+
+        IncrementalSearcher {
+            commands: command_tx,
+            results: result_rx,
+            worker: Some(worker),
+        }
+    }
+
+    /// Submits a new query. The worker re-ranks the candidate set in the
+    /// background; the corresponding results arrive via `next_results`.
+    pub fn set_query(&self, query: impl Into<String>) {
+        let _ = self.commands.send(Command::Query(query.into()));
+    }
+
+    /// Blocks for the results of the next `set_query` call still in flight.
+    /// Returns `None` once the worker has shut down.
+    pub fn next_results(&self) -> Option<Vec<ScoredMatch>> {
+        self.results.recv().ok()
+    }
+}
+
+impl Drop for IncrementalSearcher {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Shutdown);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MatcherConfig;
+
+    fn candidates(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn sorts_by_descending_score() {
+        let pattern = Pattern::new("abc", MatcherConfig::default());
+        let results = search(&pattern, &candidates(&["xaxbxc", "abc", "axbxcx"]), 10);
+        let names: Vec<&str> = results.iter().map(|m| m.candidate.as_str()).collect();
+        assert_eq!(names, vec!["abc", "axbxcx", "xaxbxc"]);
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn ties_break_on_shorter_candidate() {
+        let pattern = Pattern::new("abc", MatcherConfig::default());
+        let results = search(&pattern, &candidates(&["abcxx", "abc"]), 10);
+        assert_eq!(results[0].score, results[1].score);
+        assert_eq!(results[0].candidate, "abc");
+        assert_eq!(results[1].candidate, "abcxx");
+    }
+
+    #[test]
+    fn drops_non_matches_and_respects_limit() {
+        let pattern = Pattern::new("abc", MatcherConfig::default());
+        let results = search(&pattern, &candidates(&["abc", "nope", "cab", "xabc"]), 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].candidate, "abc");
+    }
+
+    #[test]
+    fn incremental_searcher_rereanks_without_rereading_candidates() {
+        let searcher = IncrementalSearcher::spawn(
+            candidates(&["apple", "banana", "avocado"]),
+            10,
+            |query| Pattern::new(query, MatcherConfig::default()),
+        );
+
+        searcher.set_query("a");
+        let first = searcher.next_results().unwrap();
+        assert_eq!(first.len(), 3);
+
+        searcher.set_query("ban");
+        let second = searcher.next_results().unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].candidate, "banana");
+    }
+}
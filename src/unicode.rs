@@ -50,3 +50,102 @@ pub fn next_code_point(bytes: &[u8]) -> Option<char> {
 
     Some(unsafe { char::from_u32_unchecked(ch) })
 }
+
+/// Uppercase ranges that simple-case-fold to a lowercase codepoint at a fixed
+/// offset, taken from the Unicode `CaseFolding.txt` "C" (common) mappings for
+/// the blocks most likely to show up in file names and identifiers: Latin-1
+/// Supplement, Greek, and Cyrillic. Only the +0x20 runs below are covered; other
+/// capitals in those blocks (e.g. Cyrillic U+0400-U+040F or accented Greek
+/// capitals) are left unfolded. ASCII is handled separately by `fold_case`.
+const CASE_FOLD_RANGES: &[(char, char, u32)] = &[
+    ('\u{00C0}', '\u{00D6}', 0x20), // À-Ö -> à-ö
+    ('\u{00D8}', '\u{00DE}', 0x20), // Ø-Þ -> ø-þ
+    ('\u{0391}', '\u{03A1}', 0x20), // Α-Ρ -> α-ρ
+    ('\u{03A3}', '\u{03AB}', 0x20), // Σ-Ϋ -> σ-ϋ
+    ('\u{0410}', '\u{042F}', 0x20), // А-Я -> а-я
+];
+
+/// Folds `c` to a canonical lowercase form for case-insensitive comparisons. ASCII
+/// letters are folded inline; everything else falls back to `CASE_FOLD_RANGES`.
+#[inline]
+pub fn fold_case(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    for &(start, end, offset) in CASE_FOLD_RANGES {
+        if c >= start && c <= end {
+            return char::from_u32(c as u32 + offset).unwrap_or(c);
+        }
+    }
+    c
+}
+
+/// Maps a lowercase accented letter to its plain ASCII base form (e.g. 'é' -> 'e'),
+/// so `normalize_unicode` can make "cafe" match "café". Entries are lowercase only:
+/// callers are expected to run `fold_case` first. Generated from the Unicode Latin-1
+/// Supplement block; sorted by the accented codepoint for binary search.
+const DIACRITIC_TABLE: &[(char, char)] = &[
+    ('\u{00E0}', 'a'), ('\u{00E1}', 'a'), ('\u{00E2}', 'a'), ('\u{00E3}', 'a'),
+    ('\u{00E4}', 'a'), ('\u{00E5}', 'a'), ('\u{00E6}', 'a'),
+    ('\u{00E7}', 'c'),
+    ('\u{00E8}', 'e'), ('\u{00E9}', 'e'), ('\u{00EA}', 'e'), ('\u{00EB}', 'e'),
+    ('\u{00EC}', 'i'), ('\u{00ED}', 'i'), ('\u{00EE}', 'i'), ('\u{00EF}', 'i'),
+    ('\u{00F1}', 'n'),
+    ('\u{00F2}', 'o'), ('\u{00F3}', 'o'), ('\u{00F4}', 'o'), ('\u{00F5}', 'o'),
+    ('\u{00F6}', 'o'), ('\u{00F8}', 'o'),
+    ('\u{00F9}', 'u'), ('\u{00FA}', 'u'), ('\u{00FB}', 'u'), ('\u{00FC}', 'u'),
+    ('\u{00FD}', 'y'), ('\u{00FF}', 'y'),
+];
+
+/// Strips a diacritic from `c`, leaving it untouched if it has none.
+#[inline]
+pub fn strip_diacritics(c: char) -> char {
+    match DIACRITIC_TABLE.binary_search_by_key(&c, |&(accented, _)| accented) {
+        Ok(i) => DIACRITIC_TABLE[i].1,
+        Err(_) => c,
+    }
+}
+
+/// A haystack decoded once into `char`s, shared across every term of a `Pattern` so
+/// an N-term query doesn't re-walk the same UTF-8 bytes N times. File paths and
+/// identifiers are overwhelmingly ASCII, so `new` takes a fast path for them that
+/// skips `next_code_point` entirely.
+#[derive(Debug, Clone)]
+pub struct Utf32Input {
+    chars: Vec<char>,
+}
+
+impl Utf32Input {
+    pub fn new(input: &[u8]) -> Self {
+        if input.is_ascii() {
+            return Utf32Input { chars: input.iter().map(|&b| b as char).collect() };
+        }
+        let mut chars = Vec::with_capacity(input.len());
+        let mut byte_index = 0;
+        while byte_index < input.len() {
+            if let Some(c) = next_code_point(&input[byte_index..input.len()]) {
+                chars.push(c);
+                byte_index += c.len_utf8();
+            } else {
+                byte_index += 1;
+            }
+        }
+        Utf32Input { chars }
+    }
+
+    pub fn new_str(input: &str) -> Self {
+        Utf32Input { chars: input.chars().collect() }
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+}